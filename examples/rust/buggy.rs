@@ -1,3 +1,25 @@
+//= {
+//=   "steps": [
+//=     {
+//=       "label": "attach-lldb",
+//=       "cmd": "attach-lldb",
+//=       "args": "$TARGET_BIN",
+//=       "expect": {"status": "connected"}
+//=     },
+//=     {
+//=       "label": "continue",
+//=       "cmd": "c",
+//=       "expect": {"status": "crashed"}
+//=     },
+//=     {
+//=       "label": "trace",
+//=       "cmd": "trace",
+//=       "args": "5",
+//=       "expect": {"output": {"stderr": "attempt to divide by zero"}}
+//=     }
+//=   ]
+//= }
+
 fn compute_average(total: i64, count: i64) -> i64 {
     // BUG: `count` can be zero, and this integer division will panic.
     total / count
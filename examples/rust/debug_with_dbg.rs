@@ -1,11 +1,22 @@
+mod logging;
+mod protocol;
+mod scenario;
+mod signals;
+mod targets;
+mod transport;
+
 use std::env;
 use std::fs;
-use std::io::{BufRead, BufReader, ErrorKind, Write};
-use std::os::unix::net::UnixStream;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+use logging::{Level, Logger, Record, Sink};
+use protocol::{Command as DaemonCommand, Response};
+use transport::Endpoint;
 
 const DEFAULT_DBG_SOCK: &str = "/tmp/dbg-rust.sock";
 const DEFAULT_DBG_EVENTS_DB: &str = "/tmp/dbg-rust-events.db";
@@ -14,21 +25,48 @@ const COMMAND_WRITE_TIMEOUT: Duration = Duration::from_secs(5);
 const COMMAND_RETRY_ATTEMPTS: usize = 3;
 const POLL_INTERVAL: Duration = Duration::from_millis(250);
 const PAUSE_WAIT_POLLS: usize = 60; // 15 seconds at 250ms
+const INTERRUPTED_ERROR: &str = "interrupted by signal";
 
 #[derive(Clone, Debug)]
 struct RunConfig {
     workspace_root: PathBuf,
     dbg_sock: String,
+    dbg_endpoint: Endpoint,
     dbg_events_db: String,
-    target_src: PathBuf,
-    target_bin: PathBuf,
+    /// Fixtures to compile and drive, one freshly attached session each.
+    /// Overridden to a single custom target by `RUST_DEBUG_TARGET`.
+    targets: Vec<targets::Target>,
+    /// Overrides the compiled binary path for a `RUST_DEBUG_TARGET` override;
+    /// unused when running the full registry.
+    target_bin_override: Option<PathBuf>,
+    /// When set (`DBG_TEST_RELOAD=1`), exercises the daemon's SIGHUP reload
+    /// path after the scenario completes and confirms the session survives it.
+    test_reload: bool,
+    /// Flipped by the SIGINT/SIGTERM handler; polling loops check this and
+    /// unwind cleanly instead of leaving a stale daemon and socket behind.
+    shutdown_requested: Arc<AtomicBool>,
+    logger: Arc<Logger>,
 }
 
 fn main() {
     let config = parse_config();
 
+    if let Err(e) = signals::install(&config.shutdown_requested) {
+        eprintln!("warning: {e}");
+    }
+
     if let Err(error) = run(config.clone()) {
-        eprintln!("error: {error}");
+        if error == INTERRUPTED_ERROR {
+            eprintln!("interrupted: closing session and cleaning up");
+            let _ = close_session(&config, false);
+            remove_state_files(&config);
+            std::process::exit(130);
+        }
+
+        config.logger.log(
+            Level::Error,
+            Record { label: &error, ..Default::default() },
+        );
         eprintln!(
             "hint: on macOS, if LLDB attach is denied, grant Terminal/your shell Developer Tools access and retry"
         );
@@ -39,39 +77,20 @@ fn main() {
 
 fn run(config: RunConfig) -> Result<(), String> {
     cleanup_old_state(&config)?;
-
-    compile_target(&config)?;
     ensure_daemon_running(&config)?;
 
-    run_command_retry(
-        &config,
-        "attach-lldb",
-        &json_attach_lldb(config.target_bin.to_string_lossy().as_ref()),
-    )?;
-    let attach_status = wait_for_status(&config, "post-attach")?;
-    println!("post-attach status: {attach_status}");
-
-    run_command_retry(
-        &config,
-        "frames",
-        r#"{"cmd":"q","args":"SELECT function, file, line FROM frames LIMIT 5"}"#,
-    )?;
-
-    let threads = run_command_retry(
-        &config,
-        "threads",
-        r#"{"cmd":"q","args":"SELECT id, name FROM threads LIMIT 5"}"#,
-    )?;
-    if threads.contains(r#""rows":[]"#) {
-        return Err("thread query succeeded but returned no threads".to_string());
+    for target in &config.targets {
+        run_target(&config, target)?;
     }
 
-    ensure_paused(&config)?;
-    run_command_retry(&config, "step-over", r#"{"cmd":"n"}"#)?;
-    let post_step_status = wait_for_status(&config, "post-step")?;
-    println!("post-step status: {post_step_status}");
-
-    run_command_retry(&config, "trace", r#"{"cmd":"trace","args":"5"}"#)?;
+    if config.test_reload {
+        reload_daemon(&config)?;
+        // `wait_for_status` tolerates the transient connection refusal while
+        // the daemon re-execs after SIGHUP. Only `connected:true` is checked
+        // here; the paused frame is not compared with the one before reload.
+        let reload_status = wait_for_status(&config, "post-reload")?;
+        println!("post-reload status: {reload_status}");
+    }
 
     close_session(&config, true)?;
 
@@ -79,52 +98,115 @@ fn run(config: RunConfig) -> Result<(), String> {
     Ok(())
 }
 
+/// Compiles one fixture and drives its embedded scenario against a freshly
+/// attached session, closing that session before returning so the next
+/// target (if any) starts clean.
+fn run_target(config: &RunConfig, target: &targets::Target) -> Result<(), String> {
+    let target_src = targets::materialize(config, target)?;
+    let target_bin = config
+        .target_bin_override
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("/tmp/dbg-rust-target-{}", target.name)));
+
+    compile_target(&target_src, &target_bin)?;
+
+    let spec = scenario::parse_from_source(&target_src)?;
+    scenario::run_scenario(config, &spec, &target_bin.to_string_lossy())?;
+
+    close_session(config, false)
+}
+
 fn parse_config() -> RunConfig {
-    let workspace_root = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let args: Vec<String> = env::args().skip(1).collect();
+    let verbose = args.iter().any(|a| a == "--verbose");
+    let syslog = args.iter().any(|a| a == "--syslog");
+
+    let sink = if syslog {
+        Sink::Syslog
+    } else if let Ok(path) = env::var("DBG_LOG") {
+        Sink::JsonFile(PathBuf::from(path))
+    } else {
+        Sink::Terminal
+    };
+    let logger = Arc::new(Logger::new(sink, verbose));
 
-    let target_src = env::var("RUST_DEBUG_TARGET")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| workspace_root.join("examples/rust/target.rs"));
+    let workspace_root = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
 
-    let target_bin = env::var("RUST_DEBUG_BIN")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from("/tmp/dbg-rust-target"));
+    // `RUST_DEBUG_TARGET` overrides the fixture registry with a single
+    // custom target, for exercising one source manually.
+    let (targets, target_bin_override) = match env::var("RUST_DEBUG_TARGET") {
+        Ok(raw) => (
+            vec![targets::Target {
+                name: "custom",
+                source: targets::Source::Path(PathBuf::from(raw)),
+            }],
+            env::var("RUST_DEBUG_BIN").ok().map(PathBuf::from),
+        ),
+        Err(_) => (targets::registry(), None),
+    };
+
+    let dbg_sock = env::var("DBG_SOCK").unwrap_or_else(|_| DEFAULT_DBG_SOCK.to_string());
+    let dbg_endpoint = match env::var("DBG_ENDPOINT") {
+        Ok(raw) => Endpoint::parse(&raw).unwrap_or_else(|e| {
+            eprintln!("warning: {e}; falling back to unix socket {dbg_sock}");
+            Endpoint::unix(&dbg_sock)
+        }),
+        Err(_) => Endpoint::unix(&dbg_sock),
+    };
 
     RunConfig {
         workspace_root,
-        dbg_sock: env::var("DBG_SOCK").unwrap_or_else(|_| DEFAULT_DBG_SOCK.to_string()),
+        dbg_sock,
+        dbg_endpoint,
         dbg_events_db: env::var("DBG_EVENTS_DB")
             .unwrap_or_else(|_| DEFAULT_DBG_EVENTS_DB.to_string()),
-        target_src,
-        target_bin,
+        targets,
+        target_bin_override,
+        test_reload: env::var("DBG_TEST_RELOAD").is_ok(),
+        shutdown_requested: Arc::new(AtomicBool::new(false)),
+        logger,
     }
 }
 
 fn cleanup_old_state(config: &RunConfig) -> Result<(), String> {
+    remove_state_files(config);
+
+    // Best-effort close in case a stale daemon is still reachable.
+    let _ = close_session(config, false);
+    Ok(())
+}
+
+fn remove_state_files(config: &RunConfig) {
     let _ = fs::remove_file(&config.dbg_sock);
     for suffix in ["", "-wal", "-shm"] {
         let path = format!("{}{}", config.dbg_events_db, suffix);
         let _ = fs::remove_file(path);
     }
+}
 
-    // Best-effort close in case a stale daemon is still reachable.
-    let _ = close_session(config, false);
+/// Sleeps one poll interval, unless a signal handler has flagged a shutdown,
+/// in which case polling loops unwind with [`INTERRUPTED_ERROR`] instead.
+fn poll_tick(config: &RunConfig) -> Result<(), String> {
+    if config.shutdown_requested.load(Ordering::Relaxed) {
+        return Err(INTERRUPTED_ERROR.to_string());
+    }
+    sleep(POLL_INTERVAL);
     Ok(())
 }
 
-fn compile_target(config: &RunConfig) -> Result<(), String> {
-    if !config.target_src.exists() {
+fn compile_target(target_src: &Path, target_bin: &Path) -> Result<(), String> {
+    if !target_src.exists() {
         return Err(format!(
             "target source not found: {}",
-            config.target_src.to_string_lossy()
+            target_src.to_string_lossy()
         ));
     }
 
     let output = Command::new("rustc")
         .arg("-g")
-        .arg(&config.target_src)
+        .arg(target_src)
         .arg("-o")
-        .arg(&config.target_bin)
+        .arg(target_bin)
         .output()
         .map_err(|e| format!("failed to invoke rustc: {e}"))?;
 
@@ -135,11 +217,24 @@ fn compile_target(config: &RunConfig) -> Result<(), String> {
         ));
     }
 
-    println!("compiled target: {}", config.target_bin.to_string_lossy());
+    println!("compiled target: {}", target_bin.to_string_lossy());
     Ok(())
 }
 
 fn ensure_daemon_running(config: &RunConfig) -> Result<(), String> {
+    // `run_cli` shells out to the local Node CLI to spawn/check a daemon on
+    // this host; it has no way to bring up a daemon on another machine. For
+    // a `tcp://` endpoint, assume the remote daemon is already running
+    // (that's the whole point of pointing at one) and just confirm it's
+    // reachable over the configured endpoint instead of shelling out.
+    if let Endpoint::Tcp(addr) = &config.dbg_endpoint {
+        let status_line = DaemonCommand::new("status").to_line();
+        let response = send_socket_command(&config.dbg_endpoint, &status_line)
+            .map_err(|e| format!("remote daemon at {addr} is not reachable: {e}"))?;
+        println!("daemon status: {response}");
+        return Ok(());
+    }
+
     let output = run_cli(config, ["status"])?;
 
     if output.status.success() {
@@ -156,83 +251,163 @@ fn ensure_daemon_running(config: &RunConfig) -> Result<(), String> {
     ))
 }
 
-fn run_command_retry(config: &RunConfig, label: &str, json_line: &str) -> Result<String, String> {
-    println!("===== {label} =====");
-    println!("request: {json_line}");
+fn run_command_retry(
+    config: &RunConfig,
+    label: &str,
+    command: &DaemonCommand,
+) -> Result<String, String> {
+    let json_line = command.to_line();
 
     let mut last_error = String::new();
     for attempt in 1..=COMMAND_RETRY_ATTEMPTS {
-        let response = send_socket_command(&config.dbg_sock, json_line)?;
-        println!("response[{attempt}/{COMMAND_RETRY_ATTEMPTS}]: {response}");
+        let started = Instant::now();
+        let response = match send_socket_command(&config.dbg_endpoint, &json_line) {
+            Ok(response) => response,
+            Err(e) => {
+                // Log the label/request before propagating so a connect or
+                // read failure is still attributable to a specific command.
+                config.logger.log(
+                    Level::Warn,
+                    Record {
+                        label,
+                        attempt: Some((attempt, COMMAND_RETRY_ATTEMPTS)),
+                        request: Some(&json_line),
+                        response: Some(&format!("send failed: {e}")),
+                        ..Default::default()
+                    },
+                );
+                return Err(e);
+            }
+        };
+        let elapsed = started.elapsed();
+
+        // Full per-attempt request/response pairs are only useful when
+        // chasing a flaky retry; at the default level just the outcome
+        // (below) is logged, so `--verbose` actually changes what's shown.
+        config.logger.log(
+            Level::Debug,
+            Record {
+                label,
+                attempt: Some((attempt, COMMAND_RETRY_ATTEMPTS)),
+                request: Some(&json_line),
+                response: Some(&response),
+                elapsed: Some(elapsed),
+            },
+        );
 
-        if response_ok(&response) {
+        let parsed = Response::parse(&response)?;
+        if parsed.ok {
+            config.logger.log(
+                Level::Info,
+                Record {
+                    label,
+                    response: Some(&response),
+                    elapsed: Some(elapsed),
+                    ..Default::default()
+                },
+            );
             return Ok(response);
         }
 
-        last_error = response;
+        last_error = parsed.error.unwrap_or(response);
         if attempt < COMMAND_RETRY_ATTEMPTS {
-            sleep(POLL_INTERVAL);
+            poll_tick(config)?;
         }
     }
 
     Err(format!("command '{label}' failed after retries: {last_error}"))
 }
 
-fn send_socket_command(socket_path: &str, json_line: &str) -> Result<String, String> {
-    let mut stream = UnixStream::connect(socket_path)
-        .map_err(|e| format!("connect {socket_path} failed: {e}"))?;
-    stream
-        .set_read_timeout(Some(COMMAND_READ_TIMEOUT))
-        .map_err(|e| format!("failed to set socket read timeout: {e}"))?;
-    stream
-        .set_write_timeout(Some(COMMAND_WRITE_TIMEOUT))
-        .map_err(|e| format!("failed to set socket write timeout: {e}"))?;
-
-    stream
-        .write_all(format!("{json_line}\n").as_bytes())
-        .map_err(|e| format!("write to socket failed: {e}"))?;
-
-    let mut line = String::new();
-    let mut reader = BufReader::new(stream);
-    reader
-        .read_line(&mut line)
-        .map_err(|e| match e.kind() {
-            ErrorKind::TimedOut | ErrorKind::WouldBlock => {
-                format!("timeout waiting for daemon response on {socket_path}")
-            }
-            _ => format!("read from socket failed: {e}"),
-        })?;
-
-    if line.trim().is_empty() {
-        return Err("daemon closed socket without a response".to_string());
-    }
-
-    Ok(line.trim().to_string())
+fn send_socket_command(endpoint: &Endpoint, json_line: &str) -> Result<String, String> {
+    let mut channel = transport::connect(endpoint, COMMAND_READ_TIMEOUT, COMMAND_WRITE_TIMEOUT)?;
+    channel.send_line(json_line)?;
+    channel.read_line()
 }
 
 fn close_session(config: &RunConfig, verbose: bool) -> Result<(), String> {
-    if !Path::new(&config.dbg_sock).exists() {
-        return Ok(());
+    if let Endpoint::Unix(path) = &config.dbg_endpoint {
+        if !Path::new(path).exists() {
+            return Ok(());
+        }
     }
     if verbose {
         println!("===== close =====");
     }
-    let response = send_socket_command(&config.dbg_sock, r#"{"cmd":"close"}"#)?;
+    let response = send_socket_command(&config.dbg_endpoint, &DaemonCommand::new("close").to_line())?;
     if verbose {
         println!("response: {response}");
     }
     Ok(())
 }
 
+/// Sends the daemon's `reload` command, asking it to drain in-flight work,
+/// persist session state to the events DB, and re-exec itself in place.
+///
+/// The SIGHUP handler and re-exec live in the daemon process itself, which
+/// is outside this Rust harness; this only triggers it and (via
+/// `wait_for_status`) confirms the session comes back.
+fn reload_daemon(config: &RunConfig) -> Result<String, String> {
+    println!("===== reload =====");
+    let response = send_socket_command(&config.dbg_endpoint, &DaemonCommand::new("reload").to_line())?;
+    println!("response: {response}");
+    Ok(response)
+}
+
 fn wait_for_status(config: &RunConfig, label: &str) -> Result<String, String> {
-    println!("===== {label} =====");
+    config.logger.log(Level::Info, Record { label, ..Default::default() });
+    let status_line = DaemonCommand::new("status").to_line();
     for attempt in 1..=PAUSE_WAIT_POLLS {
-        let response = send_socket_command(&config.dbg_sock, r#"{"cmd":"status"}"#)?;
-        println!("status[{attempt}/{PAUSE_WAIT_POLLS}]: {response}");
-        if response_ok(&response) && response.contains(r#""connected":true"#) {
+        let started = Instant::now();
+        let response = match send_socket_command(&config.dbg_endpoint, &status_line) {
+            Ok(response) => response,
+            Err(e) => {
+                // Tolerate a transient connection refusal, e.g. the daemon is
+                // mid-re-exec after a reload.
+                config.logger.log(
+                    Level::Warn,
+                    Record {
+                        label: "status",
+                        attempt: Some((attempt, PAUSE_WAIT_POLLS)),
+                        response: Some(&format!("connect failed, retrying ({e})")),
+                        ..Default::default()
+                    },
+                );
+                poll_tick(config)?;
+                continue;
+            }
+        };
+        config.logger.log(
+            Level::Info,
+            Record {
+                label: "status",
+                attempt: Some((attempt, PAUSE_WAIT_POLLS)),
+                response: Some(&response),
+                elapsed: Some(started.elapsed()),
+                ..Default::default()
+            },
+        );
+        let parsed = match Response::parse(&response) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                // Tolerate a malformed/partial line read mid-re-exec the same
+                // as a transient connect failure, rather than aborting.
+                config.logger.log(
+                    Level::Warn,
+                    Record {
+                        label: "status",
+                        attempt: Some((attempt, PAUSE_WAIT_POLLS)),
+                        response: Some(&format!("malformed response, retrying ({e})")),
+                        ..Default::default()
+                    },
+                );
+                poll_tick(config)?;
+                continue;
+            }
+        };
+        if parsed.ok && parsed.is_connected() {
             return Ok(response);
         }
-        sleep(POLL_INTERVAL);
+        poll_tick(config)?;
     }
 
     Err(format!(
@@ -242,18 +417,75 @@ fn wait_for_status(config: &RunConfig, label: &str) -> Result<String, String> {
 }
 
 fn ensure_paused(config: &RunConfig) -> Result<(), String> {
-    println!("===== wait-paused =====");
+    config.logger.log(
+        Level::Info,
+        Record { label: "wait-paused", ..Default::default() },
+    );
+    let status_line = DaemonCommand::new("status").to_line();
+    let pause_line = DaemonCommand::new("pause").to_line();
     for attempt in 1..=PAUSE_WAIT_POLLS {
-        let status = send_socket_command(&config.dbg_sock, r#"{"cmd":"status"}"#)?;
-        println!("pause-check[{attempt}/{PAUSE_WAIT_POLLS}]: {status}");
-        if response_ok(&status) && status.contains(r#""status":"paused""#) {
+        let started = Instant::now();
+        let status = match send_socket_command(&config.dbg_endpoint, &status_line) {
+            Ok(status) => status,
+            Err(e) => {
+                // Tolerate a transient connection refusal, e.g. the daemon is
+                // mid-re-exec after a reload.
+                config.logger.log(
+                    Level::Warn,
+                    Record {
+                        label: "pause-check",
+                        attempt: Some((attempt, PAUSE_WAIT_POLLS)),
+                        response: Some(&format!("connect failed, retrying ({e})")),
+                        ..Default::default()
+                    },
+                );
+                poll_tick(config)?;
+                continue;
+            }
+        };
+        config.logger.log(
+            Level::Info,
+            Record {
+                label: "pause-check",
+                attempt: Some((attempt, PAUSE_WAIT_POLLS)),
+                response: Some(&status),
+                elapsed: Some(started.elapsed()),
+                ..Default::default()
+            },
+        );
+        let parsed = match Response::parse(&status) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                // Tolerate a malformed/partial line read mid-re-exec the same
+                // as a transient connect failure, rather than aborting.
+                config.logger.log(
+                    Level::Warn,
+                    Record {
+                        label: "pause-check",
+                        attempt: Some((attempt, PAUSE_WAIT_POLLS)),
+                        response: Some(&format!("malformed response, retrying ({e})")),
+                        ..Default::default()
+                    },
+                );
+                poll_tick(config)?;
+                continue;
+            }
+        };
+        if parsed.ok && parsed.is_paused() {
             return Ok(());
         }
-        if response_ok(&status) && status.contains(r#""status":"running""#) {
-            let pause_response = send_socket_command(&config.dbg_sock, r#"{"cmd":"pause"}"#)?;
-            println!("pause-request: {pause_response}");
+        if parsed.ok && parsed.is_running() {
+            let pause_response = send_socket_command(&config.dbg_endpoint, &pause_line)?;
+            config.logger.log(
+                Level::Info,
+                Record {
+                    label: "pause-request",
+                    response: Some(&pause_response),
+                    ..Default::default()
+                },
+            );
         }
-        sleep(POLL_INTERVAL);
+        poll_tick(config)?;
     }
 
     Err(format!(
@@ -262,10 +494,6 @@ fn ensure_paused(config: &RunConfig) -> Result<(), String> {
     ))
 }
 
-fn response_ok(response: &str) -> bool {
-    response.contains(r#""ok":true"#)
-}
-
 fn run_cli<I, S>(config: &RunConfig, args: I) -> Result<Output, String>
 where
     I: IntoIterator<Item = S>,
@@ -295,32 +523,6 @@ where
         .map_err(|e| format!("failed to execute node CLI: {e}"))
 }
 
-fn json_attach_lldb(path: &str) -> String {
-    format!(
-        "{{\"cmd\":\"attach-lldb\",\"args\":\"{}\"}}",
-        json_escape(path)
-    )
-}
-
-fn json_escape(input: &str) -> String {
-    let mut out = String::with_capacity(input.len() + 8);
-    for ch in input.chars() {
-        match ch {
-            '"' => out.push_str("\\\""),
-            '\\' => out.push_str("\\\\"),
-            '\n' => out.push_str("\\n"),
-            '\r' => out.push_str("\\r"),
-            '\t' => out.push_str("\\t"),
-            c if c.is_control() => {
-                let code = c as u32;
-                out.push_str(&format!("\\u{:04x}", code));
-            }
-            c => out.push(c),
-        }
-    }
-    out
-}
-
 #[allow(dead_code)]
 fn _is_executable(path: &Path) -> bool {
     path.exists()
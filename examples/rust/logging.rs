@@ -0,0 +1,171 @@
+//! Structured, level-based logging for the harness.
+//!
+//! Progress used to be narrated with bare `println!`/`eprintln!` interleaved
+//! with request/response dumps, which is unusable when driven by CI or
+//! another process and impossible to filter by severity. This module emits
+//! structured records for each command (per attempt or poll, and for its
+//! outcome) to a selectable sink: a human-readable
+//! terminal sink (the default, preserving the existing console output), a
+//! JSON-lines file, or Unix syslog.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        }
+    }
+}
+
+/// Where structured log records are written.
+#[derive(Debug)]
+pub enum Sink {
+    Terminal,
+    JsonFile(PathBuf),
+    Syslog,
+}
+
+/// One structured record describing a daemon command.
+#[derive(Default)]
+pub struct Record<'a> {
+    pub label: &'a str,
+    pub attempt: Option<(usize, usize)>,
+    pub request: Option<&'a str>,
+    pub response: Option<&'a str>,
+    pub elapsed: Option<Duration>,
+}
+
+#[derive(Serialize)]
+struct JsonRecord<'a> {
+    level: &'static str,
+    label: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attempt: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attempts_total: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elapsed_ms: Option<u128>,
+}
+
+#[derive(Debug)]
+pub struct Logger {
+    sink: Sink,
+    min_level: Level,
+}
+
+impl Logger {
+    pub fn new(sink: Sink, verbose: bool) -> Self {
+        Logger {
+            sink,
+            min_level: if verbose { Level::Debug } else { Level::Info },
+        }
+    }
+
+    pub fn log(&self, level: Level, record: Record) {
+        if level < self.min_level {
+            return;
+        }
+
+        match &self.sink {
+            Sink::Terminal => log_terminal(level, &record),
+            Sink::JsonFile(path) => log_json_file(path, level, &record),
+            Sink::Syslog => log_syslog(level, &record),
+        }
+    }
+}
+
+fn log_terminal(level: Level, record: &Record) {
+    match (record.attempt, record.request, record.response) {
+        (Some((attempt, total)), Some(request), response) => {
+            // Only the first attempt gets a header/request; retries would
+            // otherwise reprint both on every poll.
+            if attempt == 1 {
+                println!("===== {} =====", record.label);
+                println!("request: {request}");
+            }
+            if let Some(response) = response {
+                println!("response[{attempt}/{total}]: {response}");
+            }
+        }
+        (Some((attempt, total)), None, Some(response)) => {
+            println!("{}[{attempt}/{total}]: {response}", record.label);
+        }
+        (None, None, Some(response)) => {
+            println!("{}: {response}", record.label);
+        }
+        (None, None, None) => match level {
+            Level::Error => eprintln!("error: {}", record.label),
+            Level::Warn => eprintln!("warn: {}", record.label),
+            _ => println!("===== {} =====", record.label),
+        },
+        _ => println!("{}", record.label),
+    }
+}
+
+fn log_json_file(path: &std::path::Path, level: Level, record: &Record) {
+    let line = JsonRecord {
+        level: level.as_str(),
+        label: record.label,
+        attempt: record.attempt.map(|(a, _)| a),
+        attempts_total: record.attempt.map(|(_, t)| t),
+        request: record.request,
+        response: record.response,
+        elapsed_ms: record.elapsed.map(|d| d.as_millis()),
+    };
+
+    let Ok(serialized) = serde_json::to_string(&line) else {
+        return;
+    };
+
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "{serialized}");
+    }
+}
+
+fn log_syslog(level: Level, record: &Record) {
+    let message = match (record.request, record.response) {
+        (Some(request), Some(response)) => {
+            format!("{}: request={request} response={response}", record.label)
+        }
+        (Some(request), None) => format!("{}: request={request}", record.label),
+        (None, Some(response)) => format!("{}: response={response}", record.label),
+        (None, None) => record.label.to_string(),
+    };
+
+    let priority = match level {
+        Level::Debug => "debug",
+        Level::Info => "info",
+        Level::Warn => "warning",
+        Level::Error => "err",
+    };
+
+    let _ = Command::new("logger")
+        .arg("-t")
+        .arg("dbg-rust-harness")
+        .arg("-p")
+        .arg(format!("user.{priority}"))
+        .arg(message)
+        .status();
+}
@@ -0,0 +1,159 @@
+//! Typed view of the daemon's line-delimited JSON protocol.
+//!
+//! The harness used to make every control-flow decision by scanning raw
+//! response bytes with `contains()`, which breaks the moment the daemon
+//! reorders fields, adds whitespace, or nests a field differently. This
+//! module models replies and commands as real `serde` types so a reply
+//! without `ok`, or with a field of the wrong type, surfaces as a clear
+//! deserialization error instead of a silently-false substring check. The
+//! other fields are optional: when absent they read as `None` (or empty).
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A daemon reply, one per line read from the transport.
+///
+/// The daemon has been observed to emit `status` both nested
+/// (`{"status":{"status":"paused","connected":true}}`) and flat
+/// (`{"status":"paused","connected":true}`); [`RawResponse`] normalizes
+/// either wire shape into this struct so callers never branch on it.
+#[derive(Debug, Deserialize)]
+#[serde(from = "RawResponse")]
+pub struct Response {
+    pub ok: bool,
+    pub status: Option<SessionStatus>,
+    pub rows: Option<Vec<serde_json::Value>>,
+    pub error: Option<String>,
+    /// Debuggee output captured since the session started, keyed by fd
+    /// (`stdout`/`stderr`), populated in response to an `output` command.
+    pub output: HashMap<String, String>,
+}
+
+/// The `status` field of a `status` reply.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionStatus {
+    pub status: String,
+    #[serde(default)]
+    pub connected: bool,
+}
+
+/// Wire shape of a [`Response`] before its `status`/`connected` fields are
+/// normalized; see [`Response`].
+#[derive(Debug, Deserialize)]
+struct RawResponse {
+    ok: bool,
+    #[serde(default)]
+    status: Option<StatusField>,
+    /// Only present on the flat wire shape, where `connected` sits beside
+    /// `status` instead of nested inside it.
+    #[serde(default)]
+    connected: Option<bool>,
+    #[serde(default)]
+    rows: Option<Vec<serde_json::Value>>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    output: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StatusField {
+    Flat(String),
+    Nested(SessionStatus),
+}
+
+impl From<RawResponse> for Response {
+    fn from(raw: RawResponse) -> Self {
+        let status = match raw.status {
+            Some(StatusField::Nested(status)) => Some(status),
+            Some(StatusField::Flat(status)) => Some(SessionStatus {
+                status,
+                connected: raw.connected.unwrap_or(false),
+            }),
+            None => None,
+        };
+
+        Response {
+            ok: raw.ok,
+            status,
+            rows: raw.rows,
+            error: raw.error,
+            output: raw.output,
+        }
+    }
+}
+
+impl Response {
+    /// Parses a raw daemon reply line.
+    pub fn parse(line: &str) -> Result<Self, String> {
+        serde_json::from_str(line).map_err(|e| format!("malformed daemon response: {e} ({line})"))
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.status.as_ref().is_some_and(|s| s.connected)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.status.as_ref().is_some_and(|s| s.status == "paused")
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status.as_ref().is_some_and(|s| s.status == "running")
+    }
+
+    /// Whether the status string equals `expected`, for scenario specs that
+    /// poll on an arbitrary named status rather than `paused`/`running`.
+    pub fn status_is(&self, expected: &str) -> bool {
+        self.status.as_ref().is_some_and(|s| s.status == expected)
+    }
+}
+
+/// A command sent to the daemon, built with `serde_json` instead of
+/// hand-escaped format strings.
+#[derive(Serialize)]
+pub struct Command<'a> {
+    pub cmd: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args: Option<String>,
+}
+
+impl<'a> Command<'a> {
+    pub fn new(cmd: &'a str) -> Self {
+        Command { cmd, args: None }
+    }
+
+    pub fn with_args(cmd: &'a str, args: impl Into<String>) -> Self {
+        Command {
+            cmd,
+            args: Some(args.into()),
+        }
+    }
+
+    pub fn to_line(&self) -> String {
+        serde_json::to_string(self).expect("command serializes to JSON")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_status_reply() {
+        let response =
+            Response::parse(r#"{"ok":true,"status":{"status":"paused","connected":true}}"#)
+                .unwrap();
+        assert!(response.is_paused());
+        assert!(response.is_connected());
+    }
+
+    #[test]
+    fn parses_flat_status_reply() {
+        let response =
+            Response::parse(r#"{"ok":true,"status":"paused","connected":true}"#).unwrap();
+        assert!(response.is_paused());
+        assert!(response.is_connected());
+    }
+}
@@ -0,0 +1,205 @@
+//! Data-driven debugging scenarios embedded in target sources.
+//!
+//! Previously `run()` hardcoded one fixed sequence (attach, query frames,
+//! step, trace) against a single target file, so adding a new debugging
+//! scenario meant editing this harness. Instead, a target source can
+//! declare its own scenario as a block of leading `//=` lines containing a
+//! single JSON document, which this module parses into a [`Scenario`] and
+//! drives generically: contributors add `.rs` files with an embedded spec,
+//! no harness code required.
+
+use std::fs;
+use std::path::Path;
+
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::protocol::{Command as DaemonCommand, Response};
+use crate::{
+    ensure_paused, poll_tick, run_command_retry, send_socket_command, wait_for_status, RunConfig,
+    PAUSE_WAIT_POLLS, POLL_INTERVAL,
+};
+
+/// Leading-line marker a target source uses to embed its scenario spec.
+pub const SPEC_MARKER: &str = "//=";
+
+/// Placeholder substituted with the compiled target binary's path before a
+/// step's command is sent to the daemon.
+pub const TARGET_BIN_PLACEHOLDER: &str = "$TARGET_BIN";
+
+#[derive(Debug, Deserialize)]
+pub struct Scenario {
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Step {
+    pub label: String,
+    pub cmd: String,
+    #[serde(default)]
+    pub args: Option<String>,
+    #[serde(default)]
+    pub expect: Expectation,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Expectation {
+    /// Session status (e.g. `"paused"`) to poll for after the step runs.
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Exact SQL rows the step's own response must contain.
+    #[serde(default)]
+    pub rows: Option<Vec<Value>>,
+    /// When `true`, the step's own response must contain at least one row;
+    /// used where the exact rows aren't predictable (e.g. live thread ids)
+    /// but a query silently returning nothing is still a failure.
+    #[serde(default)]
+    pub rows_not_empty: bool,
+    /// Regex patterns the debuggee's captured output must match, keyed by
+    /// file descriptor (`stdout`/`stderr`).
+    #[serde(default)]
+    pub output: std::collections::HashMap<String, String>,
+}
+
+/// Parses the `//=` spec block at the top of `path` into a [`Scenario`].
+pub fn parse_from_source(path: &Path) -> Result<Scenario, String> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read target source {}: {e}", path.to_string_lossy()))?;
+
+    let mut json = String::new();
+    for line in source.lines() {
+        match line.trim_start().strip_prefix(SPEC_MARKER) {
+            Some(rest) => {
+                json.push_str(rest.trim_start());
+                json.push('\n');
+            }
+            None => break,
+        }
+    }
+
+    if json.is_empty() {
+        return Err(format!(
+            "no `{SPEC_MARKER}` scenario spec found at the top of {}",
+            path.to_string_lossy()
+        ));
+    }
+
+    serde_json::from_str(&json)
+        .map_err(|e| format!("malformed scenario spec in {}: {e}", path.to_string_lossy()))
+}
+
+/// Drives every step of `scenario` in order. Nothing is attached beforehand:
+/// the scenario's own steps attach the session (an `attach-lldb` step with
+/// `$TARGET_BIN` as its args).
+pub fn run_scenario(config: &RunConfig, scenario: &Scenario, target_bin: &str) -> Result<(), String> {
+    for step in &scenario.steps {
+        let args = step
+            .args
+            .as_deref()
+            .map(|a| a.replace(TARGET_BIN_PLACEHOLDER, target_bin));
+
+        if step.cmd == "signal" {
+            let name = args.as_deref().unwrap_or_default();
+            if !crate::signals::is_supported_signal(name) {
+                return Err(format!(
+                    "step '{}': unsupported signal '{name}'",
+                    step.label
+                ));
+            }
+        }
+
+        let command = match &args {
+            Some(args) => DaemonCommand::with_args(&step.cmd, args.clone()),
+            None => DaemonCommand::new(&step.cmd),
+        };
+
+        let response = run_command_retry(config, &step.label, &command)?;
+
+        if let Some(expected_status) = &step.expect.status {
+            wait_for_scenario_status(config, &step.label, expected_status)?;
+        }
+
+        if let Some(expected_rows) = &step.expect.rows {
+            let rows = Response::parse(&response)?.rows.unwrap_or_default();
+            if &rows != expected_rows {
+                return Err(format!(
+                    "step '{}': row mismatch\n  expected: {}\n  actual:   {}",
+                    step.label,
+                    serde_json::to_string(expected_rows).unwrap_or_default(),
+                    serde_json::to_string(&rows).unwrap_or_default()
+                ));
+            }
+        }
+
+        if step.expect.rows_not_empty {
+            let rows = Response::parse(&response)?.rows.unwrap_or_default();
+            if rows.is_empty() {
+                return Err(format!(
+                    "step '{}': query succeeded but returned no rows",
+                    step.label
+                ));
+            }
+        }
+
+        for (fd, pattern) in &step.expect.output {
+            check_output_matches(config, &step.label, fd, pattern)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Polls for `expected`, reusing the existing connected/paused waiters for
+/// the two statuses the harness already knows how to nudge along, and a
+/// plain poll for anything else a scenario asks for.
+fn wait_for_scenario_status(config: &RunConfig, label: &str, expected: &str) -> Result<(), String> {
+    match expected {
+        "connected" => wait_for_status(config, label).map(|_| ()),
+        "paused" => ensure_paused(config),
+        _ => wait_for_named_status(config, label, expected),
+    }
+}
+
+fn wait_for_named_status(config: &RunConfig, label: &str, expected: &str) -> Result<(), String> {
+    let status_line = DaemonCommand::new("status").to_line();
+    for _ in 0..PAUSE_WAIT_POLLS {
+        let response = send_socket_command(&config.dbg_endpoint, &status_line)?;
+        let parsed = Response::parse(&response)?;
+        if parsed.ok && parsed.status_is(expected) {
+            return Ok(());
+        }
+        poll_tick(config)?;
+    }
+
+    Err(format!(
+        "step '{label}': status did not reach '{expected}' within {}s",
+        PAUSE_WAIT_POLLS as f64 * POLL_INTERVAL.as_secs_f64()
+    ))
+}
+
+fn check_output_matches(
+    config: &RunConfig,
+    label: &str,
+    fd: &str,
+    pattern: &str,
+) -> Result<(), String> {
+    let regex = Regex::new(pattern)
+        .map_err(|e| format!("step '{label}': invalid output regex for {fd}: {e}"))?;
+    let output_line = DaemonCommand::new("output").to_line();
+
+    for _ in 0..PAUSE_WAIT_POLLS {
+        let response = send_socket_command(&config.dbg_endpoint, &output_line)?;
+        let parsed = Response::parse(&response)?;
+        if let Some(captured) = parsed.output.get(fd) {
+            if regex.is_match(captured) {
+                return Ok(());
+            }
+        }
+        poll_tick(config)?;
+    }
+
+    Err(format!(
+        "step '{label}': output on {fd} never matched /{pattern}/"
+    ))
+}
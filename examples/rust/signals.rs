@@ -0,0 +1,36 @@
+//! Harness-side signal handling.
+//!
+//! Previously only the error path called `close_session`, so a Ctrl-C
+//! during a run left a stale daemon and socket behind. This installs a
+//! flag-based handler so an interrupted run notices at its next poll tick
+//! and can clean up its socket/events DB before exiting, instead of dying
+//! mid-command.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use signal_hook::consts::{SIGINT, SIGTERM};
+use signal_hook::flag;
+
+/// Registers SIGINT/SIGTERM to set `shutdown_requested` rather than
+/// terminating immediately.
+pub fn install(shutdown_requested: &Arc<AtomicBool>) -> Result<(), String> {
+    flag::register(SIGINT, Arc::clone(shutdown_requested))
+        .map_err(|e| format!("failed to install SIGINT handler: {e}"))?;
+    flag::register(SIGTERM, Arc::clone(shutdown_requested))
+        .map_err(|e| format!("failed to install SIGTERM handler: {e}"))?;
+    Ok(())
+}
+
+/// Signal names the daemon's `signal` command accepts for injection into the
+/// debuggee (the name-to-number mapping and LLDB delivery live in the daemon
+/// itself, outside this harness). Kept here so a typo in a scenario spec's
+/// `args` fails fast client-side instead of round-tripping to the daemon.
+pub const SUPPORTED_SIGNAL_NAMES: &[&str] = &[
+    "SIGHUP", "SIGINT", "SIGQUIT", "SIGUSR1", "SIGUSR2", "SIGTERM", "SIGCONT", "SIGSTOP",
+];
+
+/// Whether `name` is one of [`SUPPORTED_SIGNAL_NAMES`].
+pub fn is_supported_signal(name: &str) -> bool {
+    SUPPORTED_SIGNAL_NAMES.contains(&name)
+}
@@ -1,3 +1,36 @@
+//= {
+//=   "steps": [
+//=     {
+//=       "label": "attach-lldb",
+//=       "cmd": "attach-lldb",
+//=       "args": "$TARGET_BIN",
+//=       "expect": {"status": "connected"}
+//=     },
+//=     {
+//=       "label": "frames",
+//=       "cmd": "q",
+//=       "args": "SELECT function, file, line FROM frames LIMIT 5"
+//=     },
+//=     {
+//=       "label": "threads",
+//=       "cmd": "q",
+//=       "args": "SELECT id, name FROM threads LIMIT 5",
+//=       "expect": {"status": "paused", "rows_not_empty": true}
+//=     },
+//=     {
+//=       "label": "step-over",
+//=       "cmd": "n",
+//=       "expect": {"status": "connected"}
+//=     },
+//=     {
+//=       "label": "trace",
+//=       "cmd": "trace",
+//=       "args": "5",
+//=       "expect": {"output": {"stdout": "total=34"}}
+//=     }
+//=   ]
+//= }
+
 fn compute_total(input: &[i64]) -> i64 {
     let mut total = 0;
     for value in input {
@@ -0,0 +1,193 @@
+//! Registry of debugging fixtures driven by one self-check run.
+//!
+//! `compile_target` used to know about exactly one `target_src`/`target_bin`
+//! pair, so the clean sum example and the divide-by-zero panic example could
+//! never be exercised by the same invocation. A [`Target`] names a source —
+//! either a committed fixture file or a source string generated at run time
+//! — and [`registry`] lists every fixture the self-check drives in one pass,
+//! each compiled and run against its own freshly attached session.
+
+use std::path::{Path, PathBuf};
+
+use crate::RunConfig;
+
+/// Where a target's Rust source comes from.
+#[derive(Clone, Debug)]
+pub enum Source {
+    /// A committed fixture file, resolved relative to the workspace root.
+    Path(PathBuf),
+    /// Source generated at run time, written to a temp file before
+    /// compilation so throwaway fixtures don't need a committed `.rs` file.
+    Inline(&'static str),
+}
+
+#[derive(Clone, Debug)]
+pub struct Target {
+    pub name: &'static str,
+    pub source: Source,
+}
+
+impl Target {
+    fn path(name: &'static str, relative: &str) -> Self {
+        Target {
+            name,
+            source: Source::Path(PathBuf::from(relative)),
+        }
+    }
+
+    fn inline(name: &'static str, source: &'static str) -> Self {
+        Target {
+            name,
+            source: Source::Inline(source),
+        }
+    }
+}
+
+/// The fixtures exercised by one self-check run: a clean program, a
+/// panic-on-zero-count program, a deep-recursion program for `trace`, and a
+/// long-running program for signal injection.
+pub fn registry() -> Vec<Target> {
+    vec![
+        Target::path("sum", "examples/rust/target.rs"),
+        Target::path("divide-by-zero", "examples/rust/buggy.rs"),
+        Target::inline("deep-recursion", DEEP_RECURSION_SRC),
+        Target::inline("signal-stop", SIGNAL_STOP_SRC),
+    ]
+}
+
+/// Resolves `target`'s source to a concrete `.rs` file on disk, writing
+/// inline sources to a temp file first.
+pub fn materialize(config: &RunConfig, target: &Target) -> Result<PathBuf, String> {
+    match &target.source {
+        Source::Path(relative) => Ok(config.workspace_root.join(relative)),
+        Source::Inline(source) => {
+            let path = inline_target_path(target.name);
+            std::fs::write(&path, source).map_err(|e| {
+                format!(
+                    "failed to write inline target '{}' to {}: {e}",
+                    target.name,
+                    path.to_string_lossy()
+                )
+            })?;
+            Ok(path)
+        }
+    }
+}
+
+/// The path an inline target's generated source is written to.
+fn inline_target_path(name: &str) -> PathBuf {
+    Path::new("/tmp").join(format!("dbg-rust-target-{name}.rs"))
+}
+
+const DEEP_RECURSION_SRC: &str = r#"//= {
+//=   "steps": [
+//=     {
+//=       "label": "attach-lldb",
+//=       "cmd": "attach-lldb",
+//=       "args": "$TARGET_BIN",
+//=       "expect": {"status": "connected"}
+//=     },
+//=     {
+//=       "label": "frames",
+//=       "cmd": "q",
+//=       "args": "SELECT function, file, line FROM frames LIMIT 5"
+//=     },
+//=     {
+//=       "label": "threads",
+//=       "cmd": "q",
+//=       "args": "SELECT id, name FROM threads LIMIT 5",
+//=       "expect": {"status": "paused", "rows_not_empty": true}
+//=     },
+//=     {
+//=       "label": "step-over",
+//=       "cmd": "n",
+//=       "expect": {"status": "connected"}
+//=     },
+//=     {
+//=       "label": "trace",
+//=       "cmd": "trace",
+//=       "args": "60",
+//=       "expect": {"output": {"stdout": "result=820"}}
+//=     }
+//=   ]
+//= }
+
+fn recurse(depth: u32, acc: u64) -> u64 {
+    if depth == 0 {
+        acc
+    } else {
+        recurse(depth - 1, acc + depth as u64)
+    }
+}
+
+fn main() {
+    let depth = 40;
+    let result = recurse(depth, 0);
+    println!("depth={depth} result={result}");
+}
+"#;
+
+/// A program that sleeps long enough for the harness to deliver a signal to
+/// it mid-run via the daemon's `signal` command, then prints and exits.
+const SIGNAL_STOP_SRC: &str = r#"//= {
+//=   "steps": [
+//=     {
+//=       "label": "attach-lldb",
+//=       "cmd": "attach-lldb",
+//=       "args": "$TARGET_BIN",
+//=       "expect": {"status": "connected"}
+//=     },
+//=     {
+//=       "label": "continue",
+//=       "cmd": "c",
+//=       "expect": {"status": "running"}
+//=     },
+//=     {
+//=       "label": "signal",
+//=       "cmd": "signal",
+//=       "args": "SIGUSR1",
+//=       "expect": {"status": "signaled"}
+//=     },
+//=     {
+//=       "label": "resume",
+//=       "cmd": "c",
+//=       "expect": {"status": "connected"}
+//=     },
+//=     {
+//=       "label": "trace",
+//=       "cmd": "trace",
+//=       "args": "5",
+//=       "expect": {"output": {"stdout": "done waiting"}}
+//=     }
+//=   ]
+//= }
+
+use std::os::raw::c_int;
+use std::thread::sleep;
+use std::time::Duration;
+
+// `compile_target` invokes plain `rustc`, not cargo, so this fixture can't
+// pull in the `signal-hook` crate the harness itself uses; these are the raw
+// libc bindings for installing a no-op SIGUSR1 handler, so that when the
+// harness resumes after the signal stop the program keeps running instead
+// of taking the default disposition (terminate).
+const SIGUSR1: c_int = 10;
+
+extern "C" {
+    fn signal(signum: c_int, handler: usize) -> usize;
+}
+
+extern "C" fn handle_sigusr1(_: c_int) {}
+
+fn main() {
+    unsafe {
+        signal(SIGUSR1, handle_sigusr1 as *const () as usize);
+    }
+
+    println!("waiting for signal");
+    for _ in 0..50 {
+        sleep(Duration::from_millis(200));
+    }
+    println!("done waiting");
+}
+"#;
@@ -0,0 +1,135 @@
+//! Pluggable transport for talking to the `dbg` daemon.
+//!
+//! The daemon speaks a line-delimited JSON protocol over a byte stream. This
+//! module abstracts over how that stream is obtained so the harness can
+//! attach to a local Unix socket or a remote TCP daemon identically, selected
+//! via `DBG_ENDPOINT` (`unix:///tmp/dbg-rust.sock` or `tcp://host:4000`).
+
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+/// A parsed `DBG_ENDPOINT` value.
+#[derive(Clone, Debug)]
+pub enum Endpoint {
+    Unix(String),
+    Tcp(String),
+}
+
+impl Endpoint {
+    /// Parses `unix:///path/to.sock` or `tcp://host:port`.
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        if let Some(path) = raw.strip_prefix("unix://") {
+            return Ok(Endpoint::Unix(path.to_string()));
+        }
+        if let Some(addr) = raw.strip_prefix("tcp://") {
+            return Ok(Endpoint::Tcp(addr.to_string()));
+        }
+        Err(format!(
+            "unrecognized DBG_ENDPOINT scheme (expected unix:// or tcp://): {raw}"
+        ))
+    }
+
+    /// The default endpoint: a Unix socket at `dbg_sock`.
+    pub fn unix(dbg_sock: &str) -> Self {
+        Endpoint::Unix(dbg_sock.to_string())
+    }
+}
+
+/// A connected, line-oriented channel to the daemon.
+///
+/// `UnixStream` and `TcpStream` connections are both wrapped in the same
+/// generic `StreamTransport` implementation of this trait, so the rest of
+/// the harness only ever talks to `dyn Transport` and never branches on the
+/// underlying socket kind.
+pub trait Transport {
+    fn send_line(&mut self, line: &str) -> Result<(), String>;
+    fn read_line(&mut self) -> Result<String, String>;
+}
+
+struct StreamTransport<S> {
+    writer: S,
+    reader: BufReader<S>,
+}
+
+impl<S: Write + Read + CloneStream> StreamTransport<S> {
+    fn new(stream: S) -> Result<Self, String> {
+        let reader = BufReader::new(stream.try_clone_stream()?);
+        Ok(StreamTransport { writer: stream, reader })
+    }
+}
+
+trait CloneStream: Sized {
+    fn try_clone_stream(&self) -> Result<Self, String>;
+}
+
+impl CloneStream for UnixStream {
+    fn try_clone_stream(&self) -> Result<Self, String> {
+        self.try_clone()
+            .map_err(|e| format!("failed to clone unix socket: {e}"))
+    }
+}
+
+impl CloneStream for TcpStream {
+    fn try_clone_stream(&self) -> Result<Self, String> {
+        self.try_clone()
+            .map_err(|e| format!("failed to clone tcp socket: {e}"))
+    }
+}
+
+impl<S: Write + Read> Transport for StreamTransport<S> {
+    fn send_line(&mut self, line: &str) -> Result<(), String> {
+        self.writer
+            .write_all(format!("{line}\n").as_bytes())
+            .map_err(|e| format!("write to socket failed: {e}"))
+    }
+
+    fn read_line(&mut self) -> Result<String, String> {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).map_err(|e| match e.kind() {
+            ErrorKind::TimedOut | ErrorKind::WouldBlock => {
+                "timeout waiting for daemon response".to_string()
+            }
+            _ => format!("read from socket failed: {e}"),
+        })?;
+
+        if line.trim().is_empty() {
+            return Err("daemon closed socket without a response".to_string());
+        }
+
+        Ok(line.trim().to_string())
+    }
+}
+
+/// Connects to `endpoint`, applying the given per-command read/write timeouts.
+pub fn connect(
+    endpoint: &Endpoint,
+    read_timeout: Duration,
+    write_timeout: Duration,
+) -> Result<Box<dyn Transport>, String> {
+    match endpoint {
+        Endpoint::Unix(path) => {
+            let stream =
+                UnixStream::connect(path).map_err(|e| format!("connect {path} failed: {e}"))?;
+            stream
+                .set_read_timeout(Some(read_timeout))
+                .map_err(|e| format!("failed to set socket read timeout: {e}"))?;
+            stream
+                .set_write_timeout(Some(write_timeout))
+                .map_err(|e| format!("failed to set socket write timeout: {e}"))?;
+            Ok(Box::new(StreamTransport::new(stream)?))
+        }
+        Endpoint::Tcp(addr) => {
+            let stream =
+                TcpStream::connect(addr).map_err(|e| format!("connect {addr} failed: {e}"))?;
+            stream
+                .set_read_timeout(Some(read_timeout))
+                .map_err(|e| format!("failed to set socket read timeout: {e}"))?;
+            stream
+                .set_write_timeout(Some(write_timeout))
+                .map_err(|e| format!("failed to set socket write timeout: {e}"))?;
+            Ok(Box::new(StreamTransport::new(stream)?))
+        }
+    }
+}